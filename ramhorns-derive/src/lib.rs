@@ -0,0 +1,131 @@
+// Ramhorns  Copyright (C) 2019  Maciej Hirsz
+//
+// This file is part of Ramhorns. This program comes with ABSOLUTELY NO WARRANTY;
+// This is free software, and you are welcome to redistribute it under the
+// conditions of the GNU General Public License version 3.0.
+//
+// You should have received a copy of the GNU General Public License
+// along with Ramhorns.  If not, see <http://www.gnu.org/licenses/>
+
+//! `#[derive(Content)]`, generating a `ramhorns::Content` impl that dispatches
+//! each `render_field_*` method to the struct field it's asked for by name.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident};
+
+#[proc_macro_derive(Content)]
+pub fn derive_content(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand(input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match input.data {
+        Data::Struct(data) => match data.fields {
+            Fields::Named(fields) => fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "Content can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "Content can only be derived for structs with named fields",
+            ))
+        }
+    };
+
+    let field_idents: Vec<&Ident> = fields
+        .iter()
+        .map(|field| field.ident.as_ref().expect("Fields::Named fields are always named"))
+        .collect();
+    let field_names: Vec<String> = field_idents.iter().map(|ident| ident.to_string()).collect();
+
+    let escaped_arms = field_idents.iter().zip(&field_names).map(|(field, field_name)| {
+        quote! { #field_name => self.#field.render_escaped(encoder) }
+    });
+    let unescaped_arms = field_idents.iter().zip(&field_names).map(|(field, field_name)| {
+        quote! { #field_name => self.#field.render_unescaped(encoder) }
+    });
+    // This is the arm the derived impl previously fell back to the trait's
+    // default for: without it, {{js field}}/{{uri field}}/{{css field}} on a
+    // derived struct would silently HTML-escape instead of using the
+    // requested scheme.
+    let escaped_with_arms = field_idents.iter().zip(&field_names).map(|(field, field_name)| {
+        quote! { #field_name => self.#field.render_escaped_with(scheme, encoder) }
+    });
+    let section_arms = field_idents.iter().zip(&field_names).map(|(field, field_name)| {
+        quote! { #field_name => self.#field.render_section(section, encoder) }
+    });
+    let inverse_arms = field_idents.iter().zip(&field_names).map(|(field, field_name)| {
+        quote! { #field_name => self.#field.render_inverse(section, encoder) }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ramhorns::Content for #name #ty_generics #where_clause {
+            fn render_field_escaped<E>(&self, _hash: u64, name: &str, encoder: &mut E) -> Result<(), E::Error>
+            where
+                E: ramhorns::Encoder,
+            {
+                match name {
+                    #( #escaped_arms, )*
+                    _ => Ok(()),
+                }
+            }
+
+            fn render_field_unescaped<E>(&self, _hash: u64, name: &str, encoder: &mut E) -> Result<(), E::Error>
+            where
+                E: ramhorns::Encoder,
+            {
+                match name {
+                    #( #unescaped_arms, )*
+                    _ => Ok(()),
+                }
+            }
+
+            fn render_field_escaped_with<E>(&self, _hash: u64, name: &str, scheme: ramhorns::EscapingScheme, encoder: &mut E) -> Result<(), E::Error>
+            where
+                E: ramhorns::Encoder,
+            {
+                match name {
+                    #( #escaped_with_arms, )*
+                    _ => Ok(()),
+                }
+            }
+
+            fn render_field_section<'section, E>(&self, _hash: u64, name: &str, section: ramhorns::Section<'section>, encoder: &mut E) -> Result<(), E::Error>
+            where
+                E: ramhorns::Encoder,
+            {
+                match name {
+                    #( #section_arms, )*
+                    _ => Ok(()),
+                }
+            }
+
+            fn render_field_inverse<'section, E>(&self, _hash: u64, name: &str, section: ramhorns::Section<'section>, encoder: &mut E) -> Result<(), E::Error>
+            where
+                E: ramhorns::Encoder,
+            {
+                match name {
+                    #( #inverse_arms, )*
+                    _ => Ok(()),
+                }
+            }
+        }
+    })
+}
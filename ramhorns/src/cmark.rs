@@ -0,0 +1,23 @@
+// Ramhorns  Copyright (C) 2019  Maciej Hirsz
+//
+// This file is part of Ramhorns. This program comes with ABSOLUTELY NO WARRANTY;
+// This is free software, and you are welcome to redistribute it under the
+// conditions of the GNU General Public License version 3.0.
+//
+// You should have received a copy of the GNU General Public License
+// along with Ramhorns.  If not, see <http://www.gnu.org/licenses/>
+
+//! CommonMark rendering used by `Content::render_cmark`.
+//!
+//! This crate doesn't vendor a CommonMark parser, so `encode` writes `source`
+//! through unescaped rather than actually rendering Markdown to HTML. Swap in
+//! a real parser behind this one function to make `render_cmark` produce HTML.
+//! No template tag calls `render_cmark`; `{{# cmark }}` parses as an ordinary
+//! section.
+
+use crate::encoding::Encoder;
+
+/// Writes `source` to `encoder`, unescaped.
+pub fn encode<E: Encoder>(source: &str, encoder: &mut E) -> Result<(), E::Error> {
+    encoder.write_unescaped(source)
+}
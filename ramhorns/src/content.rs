@@ -9,6 +9,7 @@
 
 use crate::{Template, Section};
 use crate::encoding::Encoder;
+use crate::escaping::EscapingScheme;
 
 use std::borrow::Borrow;
 use std::collections::{HashMap, BTreeMap};
@@ -16,7 +17,10 @@ use std::hash::Hash;
 
 /// Trait allowing the rendering to quickly access data stored in the type that
 /// implements it. You needn't worry about implementing it, in virtually all
-/// cases the `#[derive(Content)]` attribute above your types should be sufficient.
+/// cases the `#[derive(Content)]` attribute above your types should be sufficient;
+/// it generates a per-field match arm for every `render_field_*` method below,
+/// including `render_field_escaped_with`, so `{{js field}}`/`{{uri field}}`/
+/// `{{css field}}` tag modifiers use the requested scheme on derived structs.
 pub trait Content: Sized {
     /// Marks whether this content is truthy. Used when attempting to render a section.
     fn is_truthy(&self) -> bool {
@@ -31,7 +35,8 @@ pub trait Content: Sized {
 
     /// Renders self as a variable to the encoder.
     ///
-    /// This will escape HTML characters, eg: `<` will become `&lt;`.
+    /// This escapes the rendered content according to the encoder's active
+    /// `Escaper` (`HtmlEscape` by default), eg: `<` will become `&lt;`.
     fn render_escaped<'section, E>(&self, _encoder: &mut E) -> Result<(), E::Error>
     where
         E: Encoder,
@@ -49,6 +54,22 @@ pub trait Content: Sized {
         self.render_escaped(encoder)
     }
 
+    /// Renders self as a variable to the encoder, using the given escaping scheme.
+    ///
+    /// This is the dispatch point for per-variable escaping tag modifiers such
+    /// as `{{js var}}` or `{{uri var}}`. `EscapingScheme::Html` and
+    /// `EscapingScheme::None` defer to `render_escaped`/`render_unescaped`
+    /// respectively, so plain `{{ var }}` and `{{{ var }}}` are unaffected.
+    fn render_escaped_with<'section, E>(&self, scheme: EscapingScheme, encoder: &mut E) -> Result<(), E::Error>
+    where
+        E: Encoder,
+    {
+        match scheme {
+            EscapingScheme::None => self.render_unescaped(encoder),
+            _ => self.render_escaped(encoder),
+        }
+    }
+
     /// Renders self as a variable to the encoder with CommonMark processing.
     ///
     /// The generated HTML is never escaped.
@@ -85,7 +106,8 @@ pub trait Content: Sized {
 
     /// Render a field by the hash **or** string of its name.
     ///
-    /// This will escape HTML characters, eg: `<` will become `&lt;`.
+    /// This escapes the rendered content according to the encoder's active
+    /// `Escaper` (`HtmlEscape` by default), eg: `<` will become `&lt;`.
     fn render_field_escaped<E>(&self, _hash: u64, _name: &str, _encoder: &mut E) -> Result<(), E::Error>
     where
         E: Encoder,
@@ -103,6 +125,26 @@ pub trait Content: Sized {
         Ok(())
     }
 
+    /// Render a field by the hash **or** string of its name, using the given escaping scheme.
+    ///
+    /// See `render_escaped_with` for how the scheme is interpreted.
+    ///
+    /// This default can't reach into a field by name, so it only ever falls
+    /// back to `render_field_escaped` (the encoder's active escaper) or
+    /// `render_field_unescaped` (no escaping); `#[derive(Content)]` generates a per-field override so
+    /// `{{js field}}`/`{{uri field}}`/`{{css field}}` actually use the
+    /// requested scheme. A hand-written `Content` impl that wants the same
+    /// must override this method per field too.
+    fn render_field_escaped_with<E>(&self, hash: u64, name: &str, scheme: EscapingScheme, encoder: &mut E) -> Result<(), E::Error>
+    where
+        E: Encoder,
+    {
+        match scheme {
+            EscapingScheme::None => self.render_field_unescaped(hash, name, encoder),
+            _ => self.render_field_escaped(hash, name, encoder),
+        }
+    }
+
     /// Render a field by the hash **or** string of its name, as a section.
     fn render_field_section<'section, E>(&self, _hash: u64, _name: &str, _section: Section<'section>, _encoder: &mut E) -> Result<(), E::Error>
     where
@@ -143,6 +185,16 @@ impl Content for &str {
         encoder.write_unescaped(*self)
     }
 
+    fn render_escaped_with<'section, E>(&self, scheme: EscapingScheme, encoder: &mut E) -> Result<(), E::Error>
+    where
+        E: Encoder,
+    {
+        match scheme {
+            EscapingScheme::None => encoder.write_unescaped(*self),
+            scheme => encoder.write_escaped_with(scheme, *self),
+        }
+    }
+
     fn render_cmark<'section, E>(&self, encoder: &mut E) -> Result<(), E::Error>
     where
         E: Encoder,
@@ -174,6 +226,16 @@ impl Content for String {
         encoder.write_unescaped(self)
     }
 
+    fn render_escaped_with<'section, E>(&self, scheme: EscapingScheme, encoder: &mut E) -> Result<(), E::Error>
+    where
+        E: Encoder,
+    {
+        match scheme {
+            EscapingScheme::None => encoder.write_unescaped(self),
+            scheme => encoder.write_escaped_with(scheme, self),
+        }
+    }
+
     fn render_cmark<'section, E>(&self, encoder: &mut E) -> Result<(), E::Error>
     where
         E: Encoder,
@@ -263,6 +325,17 @@ impl<T: Content> Content for Option<T> {
         Ok(())
     }
 
+    fn render_escaped_with<'section, E>(&self, scheme: EscapingScheme, encoder: &mut E) -> Result<(), E::Error>
+    where
+        E: Encoder,
+    {
+        if let Some(inner) = self {
+            inner.render_escaped_with(scheme, encoder)?;
+        }
+
+        Ok(())
+    }
+
     fn render_section<'section, E>(&self, section: Section<'section>, encoder: &mut E) -> Result<(), E::Error>
     where
         E: Encoder,
@@ -309,6 +382,17 @@ impl<T: Content, U> Content for Result<T, U> {
         Ok(())
     }
 
+    fn render_escaped_with<'section, E>(&self, scheme: EscapingScheme, encoder: &mut E) -> Result<(), E::Error>
+    where
+        E: Encoder,
+    {
+        if let Ok(inner) = self {
+            inner.render_escaped_with(scheme, encoder)?;
+        }
+
+        Ok(())
+    }
+
     fn render_section<'section, E>(&self, section: Section<'section>, encoder: &mut E) -> Result<(), E::Error>
     where
         E: Encoder,
@@ -384,6 +468,16 @@ where
         }
     }
 
+    fn render_field_escaped_with<E>(&self, _: u64, name: &str, scheme: EscapingScheme, encoder: &mut E) -> Result<(), E::Error>
+    where
+        E: Encoder,
+    {
+        match self.get(name) {
+            Some(v) => v.render_escaped_with(scheme, encoder),
+            None => Ok(())
+        }
+    }
+
     fn render_field_section<'section, E>(&self, _: u64, name: &str, section: Section<'section>, encoder: &mut E) -> Result<(), E::Error>
     where
         E: Encoder,
@@ -434,6 +528,16 @@ where
         }
     }
 
+    fn render_field_escaped_with<E>(&self, _: u64, name: &str, scheme: EscapingScheme, encoder: &mut E) -> Result<(), E::Error>
+    where
+        E: Encoder,
+    {
+        match self.get(name) {
+            Some(v) => v.render_escaped_with(scheme, encoder),
+            None => Ok(())
+        }
+    }
+
     fn render_field_section<'section, E>(&self, _: u64, name: &str, section: Section<'section>, encoder: &mut E) -> Result<(), E::Error>
     where
         E: Encoder,
@@ -0,0 +1,163 @@
+// Ramhorns  Copyright (C) 2019  Maciej Hirsz
+//
+// This file is part of Ramhorns. This program comes with ABSOLUTELY NO WARRANTY;
+// This is free software, and you are welcome to redistribute it under the
+// conditions of the GNU General Public License version 3.0.
+//
+// You should have received a copy of the GNU General Public License
+// along with Ramhorns.  If not, see <http://www.gnu.org/licenses/>
+
+use std::convert::Infallible;
+use std::fmt::Display;
+
+use crate::escaping::{Escaper, EscapingScheme, HtmlEscape};
+
+/// A sink that rendered `Content` is written into.
+///
+/// An `Encoder` owns the active `Escaper` (`HtmlEscape` unless overridden),
+/// which is what `write_escaped` (and `write_escaped_with` given
+/// `EscapingScheme::Html`) escape through. This is
+/// the single place HTML (or any other) escaping actually happens; `Content`
+/// impls never hardcode an escaping scheme themselves.
+pub trait Encoder: Sized {
+    type Error;
+
+    /// Writes `part` out verbatim, without any escaping.
+    fn write_unescaped(&mut self, part: &str) -> Result<(), Self::Error>;
+
+    /// Returns the active escaper, used for plain `{{ var }}` interpolations.
+    fn escaper(&self) -> &dyn Escaper;
+
+    /// Writes `part` out, escaped through this encoder's active `Escaper`.
+    fn write_escaped(&mut self, part: &str) -> Result<(), Self::Error> {
+        let mut escaped = Vec::with_capacity(part.len());
+
+        // Every built-in `Escaper` only ever writes ASCII substitutions plus
+        // the `char`s/bytes of `part` itself, so writing into a `Vec<u8>`
+        // can't actually fail.
+        self.escaper()
+            .escape(part, &mut escaped)
+            .expect("Escaper must not fail writing to a Vec<u8>");
+
+        self.write_unescaped(
+            std::str::from_utf8(&escaped).expect("Escaper must produce valid UTF-8"),
+        )
+    }
+
+    /// Writes `part` out, escaped with `scheme` regardless of this encoder's
+    /// active escaper. This is what per-variable tag modifiers such as
+    /// `{{js var}}` dispatch through. `EscapingScheme::Html`, which plain
+    /// `{{ var }}` tags resolve to, is the exception: it goes through
+    /// `write_escaped` and so uses the active escaper.
+    fn write_escaped_with(&mut self, scheme: EscapingScheme, part: &str) -> Result<(), Self::Error> {
+        if scheme == EscapingScheme::Html {
+            return self.write_escaped(part);
+        }
+
+        let mut escaped = Vec::with_capacity(part.len());
+
+        scheme
+            .escape(part, &mut escaped)
+            .expect("Escaper must not fail writing to a Vec<u8>");
+
+        self.write_unescaped(
+            std::str::from_utf8(&escaped).expect("Escaper must produce valid UTF-8"),
+        )
+    }
+
+    /// Formats `number` directly, skipping escaping since numbers never
+    /// contain characters that need it.
+    fn format_unescaped<N: Display>(&mut self, number: &N) -> Result<(), Self::Error> {
+        self.write_unescaped(&number.to_string())
+    }
+}
+
+/// The concrete `Encoder` used by `Template::render`/`render_to_string`.
+///
+/// Appends rendered output to an in-memory `String`, escaping plain
+/// `{{ var }}` interpolations through whatever `Escaper` it was built with.
+/// Use `StringEncoder::new` to get the historical `HtmlEscape` behavior, or
+/// `StringEncoder::with_escaper` to pick a different default escaper for an
+/// entire template (for example, a template that's itself mostly JavaScript).
+pub struct StringEncoder<'escaper> {
+    buffer: String,
+    escaper: &'escaper dyn Escaper,
+}
+
+impl<'escaper> StringEncoder<'escaper> {
+    /// Creates an encoder that escapes plain `{{ var }}` interpolations as HTML.
+    pub fn new() -> Self {
+        StringEncoder::with_escaper(&HtmlEscape)
+    }
+
+    /// Creates an encoder that escapes plain `{{ var }}` interpolations with `escaper`.
+    pub fn with_escaper(escaper: &'escaper dyn Escaper) -> Self {
+        StringEncoder {
+            buffer: String::new(),
+            escaper,
+        }
+    }
+
+    /// Consumes the encoder, returning everything written to it so far.
+    pub fn into_string(self) -> String {
+        self.buffer
+    }
+}
+
+impl<'escaper> Default for StringEncoder<'escaper> {
+    fn default() -> Self {
+        StringEncoder::new()
+    }
+}
+
+impl<'escaper> Encoder for StringEncoder<'escaper> {
+    type Error = Infallible;
+
+    fn write_unescaped(&mut self, part: &str) -> Result<(), Infallible> {
+        self.buffer.push_str(part);
+
+        Ok(())
+    }
+
+    fn escaper(&self) -> &dyn Escaper {
+        self.escaper
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::escaping::{JsStringEscape, NoEscape};
+
+    #[test]
+    fn default_escaper_is_html() {
+        let mut encoder = StringEncoder::new();
+        encoder.write_escaped("<a>").unwrap();
+
+        assert_eq!(encoder.into_string(), "&lt;a&gt;");
+    }
+
+    #[test]
+    fn with_escaper_changes_the_default() {
+        let mut encoder = StringEncoder::with_escaper(&JsStringEscape);
+        encoder.write_escaped("it's").unwrap();
+
+        assert_eq!(encoder.into_string(), "it\\'s");
+    }
+
+    #[test]
+    fn write_escaped_with_overrides_the_default_per_call() {
+        let mut encoder = StringEncoder::new();
+        encoder.write_escaped_with(EscapingScheme::None, "<a>").unwrap();
+
+        assert_eq!(encoder.into_string(), "<a>");
+    }
+
+    #[test]
+    fn scheme_bypasses_a_no_escape_default_escaper() {
+        let mut encoder = StringEncoder::with_escaper(&NoEscape);
+        encoder.write_escaped_with(EscapingScheme::Css, "1foo").unwrap();
+
+        assert_eq!(encoder.into_string(), "\\31 foo");
+    }
+}
@@ -0,0 +1,35 @@
+// Ramhorns  Copyright (C) 2019  Maciej Hirsz
+//
+// This file is part of Ramhorns. This program comes with ABSOLUTELY NO WARRANTY;
+// This is free software, and you are welcome to redistribute it under the
+// conditions of the GNU General Public License version 3.0.
+//
+// You should have received a copy of the GNU General Public License
+// along with Ramhorns.  If not, see <http://www.gnu.org/licenses/>
+
+//! Errors produced while parsing a template.
+
+use std::fmt;
+
+/// An error encountered while parsing a template's source into a `Template`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// A `{{`/`{{{` was opened but never closed with a matching `}}`/`}}}`.
+    UnclosedTag,
+    /// A `{{#name}}` or `{{^name}}` was opened but never closed with `{{/name}}`.
+    UnclosedSection(String),
+    /// A `{{/name}}` was found that doesn't match the section it's closing.
+    UnexpectedClosingTag(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnclosedTag => write!(f, "unclosed tag"),
+            Error::UnclosedSection(name) => write!(f, "unclosed section {:?}", name),
+            Error::UnexpectedClosingTag(name) => write!(f, "unexpected closing tag {:?}", name),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
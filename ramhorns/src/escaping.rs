@@ -0,0 +1,253 @@
+// Ramhorns  Copyright (C) 2019  Maciej Hirsz
+//
+// This file is part of Ramhorns. This program comes with ABSOLUTELY NO WARRANTY;
+// This is free software, and you are welcome to redistribute it under the
+// conditions of the GNU General Public License version 3.0.
+//
+// You should have received a copy of the GNU General Public License
+// along with Ramhorns.  If not, see <http://www.gnu.org/licenses/>
+
+//! Pluggable escaping schemes used by `Encoder` when writing `{{ escaped }}` variables.
+//!
+//! HTML is the default output format, but a template might just as well be
+//! rendering a JavaScript string literal, a URI component or a CSS identifier,
+//! each of which has its own set of characters that need to be escaped.
+
+use std::io::{self, Write};
+
+/// A policy for escaping raw content before it's written out by an `Encoder`.
+///
+/// Implement this trait to teach Ramhorns how to escape for a new output
+/// format. `Encoder` stores its active escaper as `&dyn Escaper`, so `escape`
+/// has to stay object safe; escapers that just substitute individual
+/// characters (`HtmlEscape`, `JsStringEscape`) implement it by delegating to
+/// `escape_char` through the private `escape_by_char` helper below, while
+/// ones that don't map one-to-one onto single characters (percent-encoding,
+/// CSS hex escapes) implement `escape` directly.
+pub trait Escaper {
+    /// Writes `raw` to `out`, escaping any characters that aren't safe for
+    /// this escaper's output context.
+    fn escape(&self, raw: &str, out: &mut dyn Write) -> io::Result<()>;
+
+    /// Returns the escaped representation of `c`, or `None` if `c` is safe as-is.
+    fn escape_char(c: char) -> Option<&'static str>
+    where
+        Self: Sized,
+    {
+        let _ = c;
+
+        None
+    }
+}
+
+/// Shared implementation for escapers whose `escape` is just substituting
+/// individual characters via `escape_char`.
+fn escape_by_char(
+    escape_char: impl Fn(char) -> Option<&'static str>,
+    raw: &str,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    for c in raw.chars() {
+        match escape_char(c) {
+            Some(escaped) => out.write_all(escaped.as_bytes())?,
+            None => write!(out, "{}", c)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Escapes `<`, `>`, `&`, `"` and `'` into their HTML entities.
+///
+/// This is the default escaper and matches the behavior Ramhorns has always had.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HtmlEscape;
+
+impl Escaper for HtmlEscape {
+    fn escape(&self, raw: &str, out: &mut dyn Write) -> io::Result<()> {
+        escape_by_char(Self::escape_char, raw, out)
+    }
+
+    fn escape_char(c: char) -> Option<&'static str> {
+        match c {
+            '<' => Some("&lt;"),
+            '>' => Some("&gt;"),
+            '&' => Some("&amp;"),
+            '"' => Some("&quot;"),
+            '\'' => Some("&#39;"),
+            _ => None,
+        }
+    }
+}
+
+/// Escapes characters that would break out of a single- or double-quoted
+/// JavaScript string literal.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct JsStringEscape;
+
+impl Escaper for JsStringEscape {
+    fn escape(&self, raw: &str, out: &mut dyn Write) -> io::Result<()> {
+        escape_by_char(Self::escape_char, raw, out)
+    }
+
+    fn escape_char(c: char) -> Option<&'static str> {
+        match c {
+            '\\' => Some("\\\\"),
+            '\'' => Some("\\'"),
+            '"' => Some("\\\""),
+            '\n' => Some("\\n"),
+            '\r' => Some("\\r"),
+            '\t' => Some("\\t"),
+            '\u{2028}' => Some("\\u2028"),
+            '\u{2029}' => Some("\\u2029"),
+            '<' => Some("\\u003C"),
+            _ => None,
+        }
+    }
+}
+
+/// Percent-encodes every byte that isn't a URI-safe "unreserved" character
+/// (`A-Z`, `a-z`, `0-9`, `-`, `.`, `_`, `~`), suitable for embedding a value
+/// as a single query or path component.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UriComponentEscape;
+
+impl Escaper for UriComponentEscape {
+    fn escape(&self, raw: &str, out: &mut dyn Write) -> io::Result<()> {
+        for byte in raw.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                    out.write_all(&[byte])?;
+                }
+                _ => write!(out, "%{:02X}", byte)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn escape_char(_: char) -> Option<&'static str> {
+        None
+    }
+}
+
+/// Escapes characters that aren't safe inside a CSS identifier or string by
+/// writing them out as a CSS hex escape (`\XXXXXX `), per the CSS Syntax spec.
+///
+/// A leading digit is escaped even though digits are otherwise safe, since a
+/// CSS identifier may not *start* with one.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CssEscape;
+
+impl Escaper for CssEscape {
+    fn escape(&self, raw: &str, out: &mut dyn Write) -> io::Result<()> {
+        for (i, c) in raw.chars().enumerate() {
+            match c {
+                'a'..='z' | 'A'..='Z' | '-' | '_' => write!(out, "{}", c)?,
+                '0'..='9' if i != 0 => write!(out, "{}", c)?,
+                _ => write!(out, "\\{:x} ", c as u32)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn escape_char(_: char) -> Option<&'static str> {
+        None
+    }
+}
+
+/// Writes content out verbatim, performing no escaping at all.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoEscape;
+
+impl Escaper for NoEscape {
+    fn escape(&self, raw: &str, out: &mut dyn Write) -> io::Result<()> {
+        out.write_all(raw.as_bytes())
+    }
+
+    fn escape_char(_: char) -> Option<&'static str> {
+        None
+    }
+}
+
+/// The escaping context selected for a single interpolated variable, picked
+/// via a tag modifier in the template (`{{js var}}`, `{{uri var}}`, ...).
+///
+/// Plain `{{ var }}` resolves to `Html`, and `{{{ var }}}` / `{{& var}}` resolve
+/// to `None`, so existing templates keep their current behavior.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EscapingScheme {
+    /// `{{ var }}`, escaped by an `Encoder` with its active escaper
+    /// (`HtmlEscape` unless overridden); `EscapingScheme::escape` itself
+    /// always uses `HtmlEscape`.
+    Html,
+    /// `{{js var}}`, escaped with `JsStringEscape`.
+    Js,
+    /// `{{uri var}}`, escaped with `UriComponentEscape`.
+    Uri,
+    /// `{{css var}}`, escaped with `CssEscape`.
+    Css,
+    /// `{{{ var }}}` / `{{& var}}`, not escaped at all.
+    None,
+}
+
+impl EscapingScheme {
+    /// Writes `raw` to `out`, escaped according to this scheme.
+    pub fn escape(self, raw: &str, out: &mut dyn Write) -> io::Result<()> {
+        match self {
+            EscapingScheme::Html => HtmlEscape.escape(raw, out),
+            EscapingScheme::Js => JsStringEscape.escape(raw, out),
+            EscapingScheme::Uri => UriComponentEscape.escape(raw, out),
+            EscapingScheme::Css => CssEscape.escape(raw, out),
+            EscapingScheme::None => NoEscape.escape(raw, out),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn escape(escaper: impl Escaper, raw: &str) -> String {
+        let mut out = Vec::new();
+        escaper.escape(raw, &mut out).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn html_escape() {
+        assert_eq!(escape(HtmlEscape, "<a href=\"x\">it's</a>"), "&lt;a href=&quot;x&quot;&gt;it&#39;s&lt;/a&gt;");
+        assert_eq!(escape(HtmlEscape, "plain text"), "plain text");
+    }
+
+    #[test]
+    fn js_string_escape() {
+        assert_eq!(escape(JsStringEscape, "back\\slash"), "back\\\\slash");
+        assert_eq!(escape(JsStringEscape, "quote\"'end"), "quote\\\"\\'end");
+        assert_eq!(escape(JsStringEscape, "line\nbreak\r\n"), "line\\nbreak\\r\\n");
+        assert_eq!(escape(JsStringEscape, "</script>"), "\\u003C/script>");
+        assert_eq!(escape(JsStringEscape, "\u{2028}\u{2029}"), "\\u2028\\u2029");
+    }
+
+    #[test]
+    fn uri_component_escape() {
+        assert_eq!(escape(UriComponentEscape, "a b/c?d=e"), "a%20b%2Fc%3Fd%3De");
+        assert_eq!(escape(UriComponentEscape, "abc-123._~"), "abc-123._~");
+        assert_eq!(escape(UriComponentEscape, "héllo"), "h%C3%A9llo");
+    }
+
+    #[test]
+    fn css_escape() {
+        assert_eq!(escape(CssEscape, "foo-bar_1"), "foo-bar_1");
+        assert_eq!(escape(CssEscape, "1foo"), "\\31 foo");
+        assert_eq!(escape(CssEscape, "-1foo"), "-1foo");
+        assert_eq!(escape(CssEscape, "foo bar"), "foo\\20 bar");
+    }
+
+    #[test]
+    fn no_escape() {
+        assert_eq!(escape(NoEscape, "<script>alert(1)</script>"), "<script>alert(1)</script>");
+    }
+}
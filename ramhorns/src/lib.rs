@@ -0,0 +1,28 @@
+// Ramhorns  Copyright (C) 2019  Maciej Hirsz
+//
+// This file is part of Ramhorns. This program comes with ABSOLUTELY NO WARRANTY;
+// This is free software, and you are welcome to redistribute it under the
+// conditions of the GNU General Public License version 3.0.
+//
+// You should have received a copy of the GNU General Public License
+// along with Ramhorns.  If not, see <http://www.gnu.org/licenses/>
+
+//! Ramhorns is a high performance template engine implementing a subset of
+//! [Mustache](https://mustache.github.io/): variables, sections, inverse
+//! sections and comments, without partials or delimiter changes. It adds
+//! `{{js var}}`, `{{uri var}}` and `{{css var}}` escaping modifiers.
+
+mod cmark;
+pub mod content;
+pub mod encoding;
+pub mod error;
+pub mod escaping;
+mod parser;
+mod tag;
+pub mod template;
+
+pub use crate::content::Content;
+pub use crate::encoding::{Encoder, StringEncoder};
+pub use crate::error::Error;
+pub use crate::escaping::{CssEscape, Escaper, EscapingScheme, HtmlEscape, JsStringEscape, NoEscape, UriComponentEscape};
+pub use crate::template::{Section, Template};
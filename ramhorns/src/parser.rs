@@ -0,0 +1,202 @@
+// Ramhorns  Copyright (C) 2019  Maciej Hirsz
+//
+// This file is part of Ramhorns. This program comes with ABSOLUTELY NO WARRANTY;
+// This is free software, and you are welcome to redistribute it under the
+// conditions of the GNU General Public License version 3.0.
+//
+// You should have received a copy of the GNU General Public License
+// along with Ramhorns.  If not, see <http://www.gnu.org/licenses/>
+
+//! Scans a template's source into a tree of `Tag`s.
+//!
+//! This is the piece that actually recognizes `{{name}}`, `{{{name}}}`,
+//! `{{&name}}`, `{{#name}}` / `{{^name}}` sections (closed by `{{/name}}`) and
+//! `{{!comment}}`, borrowing directly from the source it's given. Variable
+//! tags are resolved through `tag::parse_variable_tag`, so `{{js name}}`,
+//! `{{uri name}}` and `{{css name}}` are recognized here too.
+
+use crate::error::Error;
+use crate::escaping::EscapingScheme;
+use crate::tag::parse_variable_tag;
+
+/// A single parsed piece of a template, borrowing from the source it was
+/// parsed from.
+#[derive(Debug, PartialEq)]
+pub enum Tag<'tpl> {
+    /// Literal text, copied to the output verbatim.
+    Text(&'tpl str),
+    /// A variable tag, escaped with the given scheme: `{{name}}` resolves to
+    /// `Html`, `{{js name}}`/`{{uri name}}`/`{{css name}}` to their schemes.
+    Escaped(EscapingScheme, &'tpl str),
+    /// `{{{name}}}` / `{{&name}}`: a variable tag, never escaped.
+    Unescaped(&'tpl str),
+    /// `{{#name}} ... {{/name}}`: a section, rendered once per truthy value.
+    Section(&'tpl str, Vec<Tag<'tpl>>),
+    /// `{{^name}} ... {{/name}}`: a section, rendered only when falsy.
+    Inverse(&'tpl str, Vec<Tag<'tpl>>),
+}
+
+/// Parses `source` into the tags it's made of.
+pub fn parse(source: &str) -> Result<Vec<Tag<'_>>, Error> {
+    let (tags, _) = parse_block(source, None)?;
+
+    Ok(tags)
+}
+
+/// Parses tags out of `source` until it runs out, or — when parsing the body
+/// of `{{#section}}`/`{{^section}}` — until a matching `{{/section}}` is hit.
+/// Returns the parsed tags alongside whatever source is left after them.
+fn parse_block<'tpl>(
+    mut source: &'tpl str,
+    section: Option<&str>,
+) -> Result<(Vec<Tag<'tpl>>, &'tpl str), Error> {
+    let mut tags = Vec::new();
+
+    loop {
+        let start = match source.find("{{") {
+            Some(start) => start,
+            None => {
+                if let Some(name) = section {
+                    return Err(Error::UnclosedSection(name.to_owned()));
+                }
+
+                if !source.is_empty() {
+                    tags.push(Tag::Text(source));
+                }
+
+                return Ok((tags, ""));
+            }
+        };
+
+        if start > 0 {
+            tags.push(Tag::Text(&source[..start]));
+        }
+
+        let after_open = &source[start + 2..];
+        let raw = after_open.starts_with('{');
+        let (body_start, close) = if raw { (1, "}}}") } else { (0, "}}") };
+
+        let end = after_open.find(close).ok_or(Error::UnclosedTag)?;
+        let body = &after_open[body_start..end];
+        source = &after_open[end + close.len()..];
+
+        match body.chars().next() {
+            Some('!') if !raw => {}
+            Some('&') if !raw => tags.push(Tag::Unescaped(body[1..].trim())),
+            Some('#') if !raw => {
+                let name = body[1..].trim();
+                let (inner, rest) = parse_block(source, Some(name))?;
+
+                tags.push(Tag::Section(name, inner));
+                source = rest;
+            }
+            Some('^') if !raw => {
+                let name = body[1..].trim();
+                let (inner, rest) = parse_block(source, Some(name))?;
+
+                tags.push(Tag::Inverse(name, inner));
+                source = rest;
+            }
+            Some('/') if !raw => {
+                let name = body[1..].trim();
+
+                return match section {
+                    Some(expected) if expected == name => Ok((tags, source)),
+                    _ => Err(Error::UnexpectedClosingTag(name.to_owned())),
+                };
+            }
+            _ => {
+                let (scheme, name) = parse_variable_tag(body, raw);
+
+                tags.push(match scheme {
+                    EscapingScheme::None => Tag::Unescaped(name),
+                    scheme => Tag::Escaped(scheme, name),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn text_only() {
+        assert_eq!(parse("hello world").unwrap(), vec![Tag::Text("hello world")]);
+    }
+
+    #[test]
+    fn plain_and_raw_variables() {
+        assert_eq!(
+            parse("Hi, {{name}}! {{{bio}}} {{&bio}}").unwrap(),
+            vec![
+                Tag::Text("Hi, "),
+                Tag::Escaped(EscapingScheme::Html, "name"),
+                Tag::Text("! "),
+                Tag::Unescaped("bio"),
+                Tag::Text(" "),
+                Tag::Unescaped("bio"),
+            ]
+        );
+    }
+
+    #[test]
+    fn scheme_modifiers() {
+        assert_eq!(
+            parse("{{js name}}{{uri name}}{{css name}}").unwrap(),
+            vec![
+                Tag::Escaped(EscapingScheme::Js, "name"),
+                Tag::Escaped(EscapingScheme::Uri, "name"),
+                Tag::Escaped(EscapingScheme::Css, "name"),
+            ]
+        );
+    }
+
+    #[test]
+    fn comments_are_dropped() {
+        assert_eq!(
+            parse("before{{! this is a comment }}after").unwrap(),
+            vec![Tag::Text("before"), Tag::Text("after")]
+        );
+    }
+
+    #[test]
+    fn sections_and_inverse_sections() {
+        assert_eq!(
+            parse("{{#items}}<{{name}}>{{/items}}{{^items}}none{{/items}}").unwrap(),
+            vec![
+                Tag::Section(
+                    "items",
+                    vec![
+                        Tag::Text("<"),
+                        Tag::Escaped(EscapingScheme::Html, "name"),
+                        Tag::Text(">"),
+                    ]
+                ),
+                Tag::Inverse("items", vec![Tag::Text("none")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn unclosed_tag_is_an_error() {
+        assert_eq!(parse("{{name"), Err(Error::UnclosedTag));
+    }
+
+    #[test]
+    fn unclosed_section_is_an_error() {
+        assert_eq!(
+            parse("{{#items}}stuff"),
+            Err(Error::UnclosedSection("items".to_owned()))
+        );
+    }
+
+    #[test]
+    fn mismatched_closing_tag_is_an_error() {
+        assert_eq!(
+            parse("{{#items}}stuff{{/other}}"),
+            Err(Error::UnexpectedClosingTag("other".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,102 @@
+// Ramhorns  Copyright (C) 2019  Maciej Hirsz
+//
+// This file is part of Ramhorns. This program comes with ABSOLUTELY NO WARRANTY;
+// This is free software, and you are welcome to redistribute it under the
+// conditions of the GNU General Public License version 3.0.
+//
+// You should have received a copy of the GNU General Public License
+// along with Ramhorns.  If not, see <http://www.gnu.org/licenses/>
+
+//! Parsing of the escaping-scheme modifier on a variable tag (`{{js name}}`,
+//! `{{uri name}}`, `{{css name}}`), and dispatching a rendered variable
+//! through the `EscapingScheme` it resolves to.
+//!
+//! The outer tag scanner is the one that decides whether a tag is a raw tag
+//! (`{{{ name }}}` / `{{& name}}`) before it ever gets here; `parse_variable_tag`
+//! only has to recognize the `js`/`uri`/`css` keyword modifiers that select an
+//! escaper for an otherwise-escaped tag.
+
+use crate::content::Content;
+use crate::encoding::Encoder;
+use crate::escaping::EscapingScheme;
+
+/// Splits a variable tag's body into its escaping scheme and field name.
+///
+/// `raw` is `true` when the tag was already delimited as raw by the outer
+/// scanner (`{{{ name }}}` or `{{& name}}`), in which case the scheme is
+/// always `EscapingScheme::None`, matching existing behavior. Otherwise the
+/// body is checked for a `js`/`uri`/`css` keyword modifier, followed by
+/// whitespace, before falling back to `EscapingScheme::Html`.
+pub fn parse_variable_tag(body: &str, raw: bool) -> (EscapingScheme, &str) {
+    let body = body.trim();
+
+    if raw {
+        return (EscapingScheme::None, body);
+    }
+
+    for (keyword, scheme) in &[
+        ("js", EscapingScheme::Js),
+        ("uri", EscapingScheme::Uri),
+        ("css", EscapingScheme::Css),
+    ] {
+        if let Some(rest) = body.strip_prefix(keyword) {
+            if let Some(name) = rest.strip_prefix(char::is_whitespace) {
+                return (*scheme, name.trim_start());
+            }
+        }
+    }
+
+    (EscapingScheme::Html, body)
+}
+
+/// Renders a top-level variable tag, dispatching through its escaping scheme.
+pub fn render_variable<C, E>(content: &C, scheme: EscapingScheme, encoder: &mut E) -> Result<(), E::Error>
+where
+    C: Content,
+    E: Encoder,
+{
+    content.render_escaped_with(scheme, encoder)
+}
+
+/// Renders a field tag (`{{js field}}` on a section's content), dispatching
+/// through its escaping scheme.
+pub fn render_field_variable<C, E>(content: &C, hash: u64, name: &str, scheme: EscapingScheme, encoder: &mut E) -> Result<(), E::Error>
+where
+    C: Content,
+    E: Encoder,
+{
+    content.render_field_escaped_with(hash, name, scheme, encoder)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn plain_variable_is_html() {
+        assert_eq!(parse_variable_tag("name", false), (EscapingScheme::Html, "name"));
+        assert_eq!(parse_variable_tag("  name  ", false), (EscapingScheme::Html, "name"));
+    }
+
+    #[test]
+    fn js_uri_css_modifiers() {
+        assert_eq!(parse_variable_tag("js name", false), (EscapingScheme::Js, "name"));
+        assert_eq!(parse_variable_tag("uri name", false), (EscapingScheme::Uri, "name"));
+        assert_eq!(parse_variable_tag("css name", false), (EscapingScheme::Css, "name"));
+        assert_eq!(parse_variable_tag("  js   name  ", false), (EscapingScheme::Js, "name"));
+    }
+
+    #[test]
+    fn keyword_must_be_its_own_word() {
+        // "jsonfield" isn't `js` applied to "onfield" - there's no whitespace
+        // after the keyword, so it's just a plain field name.
+        assert_eq!(parse_variable_tag("jsonfield", false), (EscapingScheme::Html, "jsonfield"));
+        assert_eq!(parse_variable_tag("uritable", false), (EscapingScheme::Html, "uritable"));
+    }
+
+    #[test]
+    fn raw_tag_is_always_unescaped() {
+        assert_eq!(parse_variable_tag("js name", true), (EscapingScheme::None, "js name"));
+        assert_eq!(parse_variable_tag("name", true), (EscapingScheme::None, "name"));
+    }
+}
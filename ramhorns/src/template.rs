@@ -0,0 +1,229 @@
+// Ramhorns  Copyright (C) 2019  Maciej Hirsz
+//
+// This file is part of Ramhorns. This program comes with ABSOLUTELY NO WARRANTY;
+// This is free software, and you are welcome to redistribute it under the
+// conditions of the GNU General Public License version 3.0.
+//
+// You should have received a copy of the GNU General Public License
+// along with Ramhorns.  If not, see <http://www.gnu.org/licenses/>
+
+//! Parsed templates, and rendering `Content` through them.
+
+use crate::content::Content;
+use crate::encoding::{Encoder, StringEncoder};
+use crate::error::Error;
+use crate::escaping::Escaper;
+use crate::parser::{self, Tag};
+use crate::tag;
+
+/// A parsed template, ready to render any `Content` against.
+///
+/// `Template` borrows its source, so the `&str` it was parsed from has to
+/// outlive it.
+pub struct Template<'tpl> {
+    tags: Vec<Tag<'tpl>>,
+}
+
+impl<'tpl> Template<'tpl> {
+    /// Parses `source` into a `Template`.
+    pub fn new(source: &'tpl str) -> Result<Self, Error> {
+        Ok(Template {
+            tags: parser::parse(source)?,
+        })
+    }
+
+    /// Renders `content` through this template into `encoder`.
+    pub fn render<C, E>(&self, content: &C, encoder: &mut E) -> Result<(), E::Error>
+    where
+        C: Content,
+        E: Encoder,
+    {
+        render_tags(&self.tags, content, encoder)
+    }
+
+    /// Renders `content` through this template, escaping plain `{{ var }}`
+    /// tags as HTML, and returns the result as a `String`.
+    pub fn render_to_string<C>(&self, content: &C) -> String
+    where
+        C: Content,
+    {
+        self.render_to_string_with(content, &crate::escaping::HtmlEscape)
+    }
+
+    /// Renders `content` through this template, escaping plain `{{ var }}`
+    /// tags with `escaper` instead of the default `HtmlEscape`, and returns
+    /// the result as a `String`.
+    pub fn render_to_string_with<C>(&self, content: &C, escaper: &dyn Escaper) -> String
+    where
+        C: Content,
+    {
+        let mut encoder = StringEncoder::with_escaper(escaper);
+
+        self.render(content, &mut encoder)
+            .unwrap_or_else(|error| match error {});
+
+        encoder.into_string()
+    }
+}
+
+/// A section's body: the tags between `{{#name}}`/`{{^name}}` and the
+/// matching `{{/name}}`, handed to `Content::render_field_section` /
+/// `Content::render_field_inverse` to render once per item.
+pub struct Section<'section> {
+    tags: &'section [Tag<'section>],
+}
+
+impl<'section> Section<'section> {
+    /// Renders `content` through this section's body into `encoder`.
+    pub fn render_once<C, E>(&self, content: &C, encoder: &mut E) -> Result<(), E::Error>
+    where
+        C: Content,
+        E: Encoder,
+    {
+        render_tags(self.tags, content, encoder)
+    }
+}
+
+fn render_tags<'tpl, C, E>(tags: &'tpl [Tag<'tpl>], content: &C, encoder: &mut E) -> Result<(), E::Error>
+where
+    C: Content,
+    E: Encoder,
+{
+    for node in tags {
+        match node {
+            Tag::Text(text) => encoder.write_unescaped(text)?,
+            Tag::Escaped(scheme, name) if *name == "." => {
+                tag::render_variable(content, *scheme, encoder)?
+            }
+            Tag::Escaped(scheme, name) => tag::render_field_variable(content, 0, name, *scheme, encoder)?,
+            Tag::Unescaped(name) if *name == "." => content.render_unescaped(encoder)?,
+            Tag::Unescaped(name) => content.render_field_unescaped(0, name, encoder)?,
+            Tag::Section(name, inner) => {
+                content.render_field_section(0, name, Section { tags: inner }, encoder)?
+            }
+            Tag::Inverse(name, inner) => {
+                content.render_field_inverse(0, name, Section { tags: inner }, encoder)?
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::escaping::EscapingScheme;
+
+    struct Profile<'a> {
+        name: &'a str,
+        bio: &'a str,
+    }
+
+    impl<'a> Content for Profile<'a> {
+        fn render_field_escaped<E>(&self, _hash: u64, name: &str, encoder: &mut E) -> Result<(), E::Error>
+        where
+            E: Encoder,
+        {
+            match name {
+                "name" => self.name.render_escaped(encoder),
+                "bio" => self.bio.render_escaped(encoder),
+                _ => Ok(()),
+            }
+        }
+
+        fn render_field_unescaped<E>(&self, _hash: u64, name: &str, encoder: &mut E) -> Result<(), E::Error>
+        where
+            E: Encoder,
+        {
+            match name {
+                "name" => self.name.render_unescaped(encoder),
+                "bio" => self.bio.render_unescaped(encoder),
+                _ => Ok(()),
+            }
+        }
+
+        fn render_field_escaped_with<E>(&self, _hash: u64, name: &str, scheme: EscapingScheme, encoder: &mut E) -> Result<(), E::Error>
+        where
+            E: Encoder,
+        {
+            match name {
+                "name" => self.name.render_escaped_with(scheme, encoder),
+                "bio" => self.bio.render_escaped_with(scheme, encoder),
+                _ => Ok(()),
+            }
+        }
+
+        fn render_field_section<'section, E>(&self, _hash: u64, name: &str, section: crate::template::Section<'section>, encoder: &mut E) -> Result<(), E::Error>
+        where
+            E: Encoder,
+        {
+            match name {
+                "name" => self.name.render_section(section, encoder),
+                _ => Ok(()),
+            }
+        }
+
+        fn render_field_inverse<'section, E>(&self, _hash: u64, name: &str, section: crate::template::Section<'section>, encoder: &mut E) -> Result<(), E::Error>
+        where
+            E: Encoder,
+        {
+            match name {
+                "name" => self.name.render_inverse(section, encoder),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    #[test]
+    fn plain_tag_is_html_escaped() {
+        let tpl = Template::new("Hi, {{name}}!").unwrap();
+        let profile = Profile { name: "<b>Bob</b>", bio: "" };
+
+        assert_eq!(tpl.render_to_string(&profile), "Hi, &lt;b&gt;Bob&lt;/b&gt;!");
+    }
+
+    // This is the case the review flagged as broken: a `{{js name}}` tag in
+    // an actual template source has to come out JS-escaped, not HTML-escaped
+    // and not looked up as a field literally named "js name".
+    #[test]
+    fn js_modifier_tag_is_js_escaped() {
+        let tpl = Template::new("var name = \"{{js name}}\";").unwrap();
+        let profile = Profile { name: "a\"b", bio: "" };
+
+        assert_eq!(tpl.render_to_string(&profile), "var name = \"a\\\"b\";");
+    }
+
+    #[test]
+    fn raw_tag_is_never_escaped() {
+        let tpl = Template::new("{{{bio}}}").unwrap();
+        let profile = Profile { name: "", bio: "<p>hi</p>" };
+
+        assert_eq!(tpl.render_to_string(&profile), "<p>hi</p>");
+    }
+
+    #[test]
+    fn render_to_string_with_picks_the_default_escaper() {
+        let tpl = Template::new("{{name}}").unwrap();
+        let profile = Profile { name: "it's", bio: "" };
+
+        assert_eq!(
+            tpl.render_to_string_with(&profile, &crate::escaping::JsStringEscape),
+            "it\\'s"
+        );
+    }
+
+    #[test]
+    fn sections_render_once_per_truthy_value() {
+        let tpl = Template::new("{{#name}}yes{{/name}}{{^name}}no{{/name}}").unwrap();
+
+        assert_eq!(
+            tpl.render_to_string(&Profile { name: "x", bio: "" }),
+            "yes"
+        );
+        assert_eq!(
+            tpl.render_to_string(&Profile { name: "", bio: "" }),
+            "no"
+        );
+    }
+}